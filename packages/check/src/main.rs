@@ -1,26 +1,83 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs::File;
-use std::io::Read;
 use std::path::Path;
 use std::process::exit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 use anyhow::Context;
-use clap::{Arg, ArgAction, Command};
+use base64::Engine as _;
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use colored::Colorize;
+use serde::Serialize;
 
 use cosmwasm_vm::internals::{
-    check_wasm_with_limits, compile, make_compiling_engine, LogOutput, Logger,
+    check_wasm_with_limits, compile, make_compiling_engine, LogOutput, Logger, ParsedWasm,
 };
 use cosmwasm_vm::{capabilities_from_csv, Config, WasmLimits};
 
 const DEFAULT_AVAILABLE_CAPABILITIES: &str =
     "iterator,staking,stargate,cosmwasm_1_1,cosmwasm_1_2,cosmwasm_1_3,cosmwasm_1_4,cosmwasm_2_0,cosmwasm_2_1";
 
+/// The format in which the check results are emitted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputMode {
+    /// Colorized, human readable text on stdout (the default).
+    Human,
+    /// A single JSON array describing every checked contract, for consumption by CI pipelines.
+    Json,
+}
+
+impl OutputMode {
+    fn from_flag(value: &str) -> Self {
+        match value {
+            "json" => OutputMode::Json,
+            _ => OutputMode::Human,
+        }
+    }
+}
+
+/// The outcome of checking a single contract, serialized as one element of the `--output json` array.
+#[derive(Serialize)]
+struct ContractReport {
+    path: String,
+    #[serde(serialize_with = "serialize_result")]
+    result: bool,
+    error: Option<String>,
+    available_capabilities: Vec<String>,
+    required_capabilities: Vec<String>,
+    memory_pages: u32,
+    exports: Vec<String>,
+    imports: Vec<String>,
+}
+
+fn serialize_result<S: serde::Serializer>(passed: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(if *passed { "pass" } else { "fail" })
+}
+
 pub fn main() {
     let matches = Command::new("Contract checking")
         .version(env!("CARGO_PKG_VERSION"))
         .long_about("Checks the given wasm file (memories, exports, imports, available capabilities, and non-determinism).")
         .author("Mauro Lacy <mauro@lacy.com.es>")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(check_command())
+        .subcommand(inspect_command())
+        .subcommand(diff_command())
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("check", sub_matches)) => run_check(sub_matches),
+        Some(("inspect", sub_matches)) => run_inspect(sub_matches),
+        Some(("diff", sub_matches)) => run_diff(sub_matches),
+        _ => unreachable!("a subcommand is required"),
+    }
+}
+
+fn check_command() -> Command {
+    Command::new("check")
+        .about("Checks the given wasm files against a target chain's capabilities")
         .arg(
             Arg::new("CAPABILITIES")
                 // `long` setting required to turn the position argument into an option 🤷
@@ -38,6 +95,17 @@ pub fn main() {
                 .help("Prints additional information on stderr")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .value_name("MODE")
+                .help("Selects how results are emitted")
+                .long_help("Selects how results are emitted. `human` (the default) prints colorized text per contract, `json` suppresses that text and emits a single machine-readable array describing every checked contract.")
+                .value_parser(["human", "json"])
+                .default_value("human")
+                .num_args(1)
+                .action(ArgAction::Set),
+        )
         .arg(
             Arg::new("CONFIG")
             .long("wasm-config")
@@ -50,6 +118,17 @@ If this is not provided, the default values are used. This conflicts with the --
             .num_args(1)
             .action(ArgAction::Set)
         )
+        .arg(
+            Arg::new("CHAIN_RPC")
+            .long("chain-rpc")
+            .value_name("URL")
+            .conflicts_with("CONFIG")
+            .conflicts_with("CAPABILITIES")
+            .help("Query the chain's wasm limits over RPC.")
+            .long_help("Query the chain's wasm limits directly from a running node's RPC endpoint, using the WasmLimitsConfig query. This keeps the limits in sync with what the live chain enforces and avoids having to export the config to a file first. The available capabilities are not fetched from the node; the default capability set is used. This conflicts with the --wasm-config and --available-capabilities flags.")
+            .num_args(1)
+            .action(ArgAction::Set)
+        )
         .arg(
             Arg::new("WASM")
                 .help("Wasm file to read and compile")
@@ -58,69 +137,392 @@ If this is not provided, the default values are used. This conflicts with the --
                 .num_args(0..)
                 .action(ArgAction::Append),
         )
-        .get_matches();
+}
+
+fn inspect_command() -> Command {
+    Command::new("inspect")
+        .about("Dumps everything a wasm blob declares, without enforcing any capabilities")
+        .long_about("Dumps everything a wasm blob declares, without enforcing any capabilities. This reports the declared memory pages, every exported function and global, every imported host function grouped by module, and the capabilities the contract requires. Useful for understanding what a contract needs before picking a target chain.")
+        .arg(
+            Arg::new("WASM")
+                .help("Wasm file to inspect")
+                .required(true)
+                .index(1)
+                .num_args(1)
+                .action(ArgAction::Set),
+        )
+}
 
+fn diff_command() -> Command {
+    Command::new("diff")
+        .about("Compares two contract versions and reports migration-breaking changes")
+        .long_about("Compares two contract versions and reports, as a structured diff, exports and imports added/removed, required capabilities added/removed, and any change in declared memory limits. Exits non-zero when the new contract introduces a required capability or removes a previously exported entry point, so it can gate a release.")
+        .arg(
+            Arg::new("OLD")
+                .help("The old (baseline) wasm file")
+                .required(true)
+                .index(1)
+                .num_args(1)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("NEW")
+                .help("The new wasm file to compare against the baseline")
+                .required(true)
+                .index(2)
+                .num_args(1)
+                .action(ArgAction::Set),
+        )
+}
+
+fn run_check(matches: &ArgMatches) {
     let config_file = matches.get_one::<String>("CONFIG");
+    let chain_rpc = matches.get_one::<String>("CHAIN_RPC");
     let available_capabilities_csv = matches
         .get_one::<String>("CAPABILITIES")
         .map(|s| s.as_str());
+    let output_mode = OutputMode::from_flag(
+        matches
+            .get_one::<String>("OUTPUT")
+            .expect("output mode has a default"),
+    );
 
     // Available capabilities and Wasm limits
-    let (wasm_limits, available_capabilities) = match (config_file, available_capabilities_csv) {
-        (Some(config_file), _) => {
+    let (wasm_limits, available_capabilities) = match (config_file, chain_rpc, available_capabilities_csv) {
+        (Some(config_file), _, _) => {
             let config = read_config(config_file).unwrap();
             (config.wasm_limits, config.cache.available_capabilities)
         }
-        (_, available_capabilities_csv) => {
+        (_, Some(chain_rpc), _) => {
+            let wasm_limits = query_wasm_limits(chain_rpc).unwrap();
+            // The chain's WasmLimitsConfig query only exposes the wasm limits, not the node's
+            // available capabilities (those are node-local, not on-chain), so default them the
+            // same way the `--available-capabilities` flag does when left unset.
+            let available_capabilities = capabilities_from_csv(DEFAULT_AVAILABLE_CAPABILITIES);
+            (wasm_limits, available_capabilities)
+        }
+        (_, _, available_capabilities_csv) => {
             let available_capabilities = capabilities_from_csv(
                 available_capabilities_csv.unwrap_or(DEFAULT_AVAILABLE_CAPABILITIES),
             );
             (WasmLimits::default(), available_capabilities)
         }
     };
-    println!("Available capabilities: {available_capabilities:?}");
-    println!();
+    if output_mode == OutputMode::Human {
+        println!("Available capabilities: {available_capabilities:?}");
+        println!();
+    }
 
-    // File
-    let paths = matches
+    // Files
+    let paths: Vec<&String> = matches
         .get_many::<String>("WASM")
-        .expect("Error parsing file names");
-
-    let (passes, failures): (Vec<_>, _) = paths
-        .map(|p| {
-            let result = check_contract(
-                p,
-                &available_capabilities,
-                matches.get_flag("VERBOSE"),
-                &wasm_limits,
-            );
-            match &result {
-                Ok(_) => println!("{}: {}", p, "pass".green()),
-                Err(e) => {
-                    println!("{}: {}", p, "failure".red());
-                    println!("{e}");
-                }
-            };
-            result
+        .expect("Error parsing file names")
+        .collect();
+    let verbose = matches.get_flag("VERBOSE");
+
+    // Check every file on a bounded worker pool. Each file's human-facing pass/fail line is
+    // buffered and flushed in stable input order once the parallel phase completes.
+    //
+    // `--verbose` is special: the per-file `Logger` writes live to stderr (`LogOutput` has no
+    // in-memory sink to buffer and reorder), so for verbose output to come out in stable input
+    // order we fall back to a single worker. Because workers pull indices in ascending order, one
+    // worker processes files strictly in input order, which is exactly the ordering we need; the
+    // parallel pool is only used when there is no live log output to order.
+    let next = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<(usize, FileOutcome)>> = Mutex::new(Vec::with_capacity(paths.len()));
+
+    let worker_count = if verbose {
+        1
+    } else {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(paths.len().max(1))
+    };
+
+    std::thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&path) = paths.get(index) else {
+                    break;
+                };
+
+                // Read each file once. For JSON reports we parse it here for the report surface;
+                // `check_wasm_with_limits` parses again internally during the check, so JSON mode
+                // parses twice per file. That is a deliberate trade: the alternative would be to
+                // thread a `ParsedWasm` into the check, which the VM's public API does not accept.
+                let (result, analysis) = match std::fs::read(path) {
+                    Ok(wasm) => {
+                        let analysis = (output_mode == OutputMode::Json)
+                            .then(|| ContractAnalysis::from_wasm(&wasm).ok())
+                            .flatten();
+                        let result = check_contract(
+                            path,
+                            &wasm,
+                            &available_capabilities,
+                            verbose,
+                            &wasm_limits,
+                        );
+                        (result, analysis)
+                    }
+                    Err(e) => (
+                        Err(anyhow::Error::new(e).context(format!("error reading {path}"))),
+                        None,
+                    ),
+                };
+
+                let human = match &result {
+                    Ok(_) => format!("{}: {}", path, "pass".green()),
+                    Err(e) => format!("{}: {}\n{e}", path, "failure".red()),
+                };
+                let report = match output_mode {
+                    OutputMode::Human => None,
+                    OutputMode::Json => Some(ContractReport::from_analysis(
+                        path,
+                        &available_capabilities,
+                        &result,
+                        analysis.as_ref(),
+                    )),
+                };
+
+                outcomes
+                    .lock()
+                    .unwrap()
+                    .push((index, FileOutcome { result, human, report }));
+            });
+        }
+    });
+
+    // Flush in stable input order.
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    let mut reports = Vec::new();
+    let (passes, failures): (Vec<_>, Vec<_>) = outcomes
+        .into_iter()
+        .map(|(_, outcome)| {
+            match output_mode {
+                OutputMode::Human => println!("{}", outcome.human),
+                OutputMode::Json => reports.push(outcome.report.expect("json mode builds a report")),
+            }
+            outcome.result
         })
         .partition(|result| result.is_ok());
-    println!();
 
-    if failures.is_empty() {
+    match output_mode {
+        OutputMode::Human => {
+            println!();
+            if failures.is_empty() {
+                println!(
+                    "All contracts ({}) {} checks!",
+                    passes.len(),
+                    "passed".green()
+                );
+            } else {
+                println!(
+                    "{}: {}, {}: {}",
+                    "Passes".green(),
+                    passes.len(),
+                    "failures".red(),
+                    failures.len()
+                );
+            }
+        }
+        OutputMode::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&reports).expect("reports are serializable")
+            );
+        }
+    }
+
+    if !failures.is_empty() {
+        exit(1);
+    }
+}
+
+/// The buffered result of checking a single file, flushed in input order after the parallel phase.
+struct FileOutcome {
+    result: anyhow::Result<()>,
+    human: String,
+    report: Option<ContractReport>,
+}
+
+fn run_inspect(matches: &ArgMatches) {
+    let path = matches
+        .get_one::<String>("WASM")
+        .expect("Error parsing file name");
+
+    if let Err(e) = inspect_contract(path) {
+        eprintln!("{e}");
+        exit(1);
+    }
+}
+
+/// A host function imported by a contract, kept as a module/name pair so we never have to split a
+/// joined `"module.name"` string back apart (an import name may itself contain a dot).
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Import {
+    module: String,
+    name: String,
+}
+
+impl std::fmt::Display for Import {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.module, self.name)
+    }
+}
+
+/// The statically discoverable surface of a contract, used by `inspect` and `diff`.
+struct ContractAnalysis {
+    memory_min: u64,
+    memory_max: Option<u64>,
+    exports: BTreeMap<String, String>,
+    imports: BTreeSet<Import>,
+    required_capabilities: BTreeSet<String>,
+}
+
+impl ContractAnalysis {
+    fn from_wasm(wasm: &[u8]) -> anyhow::Result<Self> {
+        let parsed = ParsedWasm::parse(wasm)?;
+
+        let (memory_min, memory_max) = match parsed.memories.first() {
+            Some(memory) => (memory.initial, memory.maximum),
+            None => (0, None),
+        };
+
+        let exports = parsed
+            .exports
+            .iter()
+            .map(|e| (e.name.to_string(), format!("{:?}", e.kind)))
+            .collect();
+        let imports = parsed
+            .imports
+            .iter()
+            .map(|i| Import {
+                module: i.module.to_string(),
+                name: i.name.to_string(),
+            })
+            .collect();
+        // A contract advertises the capabilities it needs through exported `requires_*` markers.
+        let required_capabilities = parsed
+            .exports
+            .iter()
+            .filter_map(|e| e.name.strip_prefix("requires_"))
+            .filter(|capability| !capability.is_empty())
+            .map(|capability| capability.to_string())
+            .collect();
+
+        Ok(ContractAnalysis {
+            memory_min,
+            memory_max,
+            exports,
+            imports,
+            required_capabilities,
+        })
+    }
+
+    fn format_memory_max(&self) -> String {
+        self.memory_max
+            .map(|m| format!("{m} pages"))
+            .unwrap_or_else(|| "unbounded".to_string())
+    }
+}
+
+fn run_diff(matches: &ArgMatches) {
+    let old_path = matches.get_one::<String>("OLD").expect("missing old file");
+    let new_path = matches.get_one::<String>("NEW").expect("missing new file");
+
+    match diff_contracts(old_path, new_path) {
+        Ok(breaking) => {
+            if breaking {
+                exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            exit(1);
+        }
+    }
+}
+
+/// Prints the structured diff between two contracts and returns whether the change is
+/// migration-breaking (a newly required capability or a removed export).
+fn diff_contracts(old_path: &str, new_path: &str) -> anyhow::Result<bool> {
+    let old = ContractAnalysis::from_wasm(&std::fs::read(old_path)?)
+        .with_context(|| format!("error analyzing {old_path}"))?;
+    let new = ContractAnalysis::from_wasm(&std::fs::read(new_path)?)
+        .with_context(|| format!("error analyzing {new_path}"))?;
+
+    let export_names = |a: &ContractAnalysis| a.exports.keys().cloned().collect::<BTreeSet<_>>();
+    let old_exports = export_names(&old);
+    let new_exports = export_names(&new);
+
+    println!("Exports:");
+    print_set_diff(&old_exports, &new_exports);
+
+    println!("Imports:");
+    print_set_diff(&old.imports, &new.imports);
+
+    println!("Required capabilities:");
+    print_set_diff(&old.required_capabilities, &new.required_capabilities);
+
+    println!("Memory:");
+    if old.memory_min != new.memory_min || old.memory_max != new.memory_max {
         println!(
-            "All contracts ({}) {} checks!",
-            passes.len(),
-            "passed".green()
+            "    min {} pages, max {} -> min {} pages, max {}",
+            old.memory_min,
+            old.format_memory_max(),
+            new.memory_min,
+            new.format_memory_max()
         );
     } else {
-        println!(
-            "{}: {}, {}: {}",
-            "Passes".green(),
-            passes.len(),
-            "failures".red(),
-            failures.len()
-        );
-        exit(1);
+        println!("    unchanged");
+    }
+
+    let removed_exports: Vec<&String> = old_exports.difference(&new_exports).collect();
+    let added_capabilities: Vec<&String> = new
+        .required_capabilities
+        .difference(&old.required_capabilities)
+        .collect();
+
+    let breaking = !removed_exports.is_empty() || !added_capabilities.is_empty();
+    if breaking {
+        println!();
+        if !removed_exports.is_empty() {
+            println!(
+                "{}: the new contract dropped exported entry point(s): {}",
+                "migration-breaking".red(),
+                removed_exports
+                    .iter()
+                    .map(|e| e.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        if !added_capabilities.is_empty() {
+            println!(
+                "{}: the new contract now requires capability(ies): {}",
+                "migration-breaking".red(),
+                added_capabilities
+                    .iter()
+                    .map(|c| c.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    Ok(breaking)
+}
+
+fn print_set_diff<T: Ord + std::fmt::Display>(old: &BTreeSet<T>, new: &BTreeSet<T>) {
+    for added in new.difference(old) {
+        println!("    {} {added}", "+".green());
+    }
+    for removed in old.difference(new) {
+        println!("    {} {removed}", "-".red());
     }
 }
 
@@ -130,18 +532,129 @@ fn read_config(path: &str) -> anyhow::Result<Config> {
     Ok(config)
 }
 
+/// gRPC query method exposed by wasmd that returns the chain's wasm limits. Routed through
+/// CometBFT's `abci_query`, it answers with a protobuf `QueryWasmLimitsConfigResponse` whose single
+/// field is the limits serialized as JSON. (`WasmConfig` itself is node-local and not abci-queryable,
+/// and the response carries no capabilities — those are defaulted by the caller.)
+const WASM_LIMITS_CONFIG_QUERY_PATH: &str = "/cosmwasm.wasm.v1.Query/WasmLimitsConfig";
+
+/// Queries a running node's wasm limits over its CometBFT RPC endpoint, decoding the protobuf
+/// `QueryWasmLimitsConfigResponse` and parsing its JSON `config` field into [`WasmLimits`].
+fn query_wasm_limits(rpc_url: &str) -> anyhow::Result<WasmLimits> {
+    let url = format!("{}/abci_query", rpc_url.trim_end_matches('/'));
+    // `QueryWasmLimitsConfigRequest` carries no fields, so the request payload is the empty message.
+    let response: AbciQueryEnvelope = ureq::get(&url)
+        .query("path", &format!("\"{WASM_LIMITS_CONFIG_QUERY_PATH}\""))
+        .query("data", "0x")
+        .call()
+        .context("error querying chain RPC")?
+        .into_json()
+        .context("error parsing chain RPC response")?;
+
+    let response = response.result.response;
+    anyhow::ensure!(
+        response.code == 0,
+        "chain returned error code {} for the WasmLimitsConfig query: {}",
+        response.code,
+        response.log
+    );
+    let value = response.value.unwrap_or_default();
+    anyhow::ensure!(
+        !value.is_empty(),
+        "chain returned an empty WasmLimitsConfig response"
+    );
+
+    let proto = base64::engine::general_purpose::STANDARD
+        .decode(value)
+        .context("error base64-decoding WasmLimitsConfig response")?;
+    // Field 1 of `QueryWasmLimitsConfigResponse` is the JSON-encoded limits `config` string.
+    let config_json =
+        protobuf_length_delimited_field(&proto, 1).context("error decoding WasmLimitsConfig response")?;
+    let wasm_limits =
+        serde_json::from_slice(config_json).context("error parsing WasmLimitsConfig response")?;
+    Ok(wasm_limits)
+}
+
+/// Returns the bytes of the first length-delimited field with the given number, skipping over any
+/// other fields regardless of order. This tolerates a response that carries additional or
+/// reordered fields instead of assuming a single leading field.
+fn protobuf_length_delimited_field(mut bytes: &[u8], field: u64) -> anyhow::Result<&[u8]> {
+    while !bytes.is_empty() {
+        let (tag, rest) = read_varint(bytes)?;
+        bytes = rest;
+        let field_number = tag >> 3;
+        match tag & 0x7 {
+            // varint
+            0 => bytes = read_varint(bytes)?.1,
+            // 64-bit
+            1 => {
+                anyhow::ensure!(bytes.len() >= 8, "truncated protobuf response");
+                bytes = &bytes[8..];
+            }
+            // length-delimited
+            2 => {
+                let (len, rest) = read_varint(bytes)?;
+                let len = len as usize;
+                anyhow::ensure!(rest.len() >= len, "truncated protobuf response");
+                if field_number == field {
+                    return Ok(&rest[..len]);
+                }
+                bytes = &rest[len..];
+            }
+            // 32-bit
+            5 => {
+                anyhow::ensure!(bytes.len() >= 4, "truncated protobuf response");
+                bytes = &bytes[4..];
+            }
+            other => anyhow::bail!("unsupported protobuf wire type {other}"),
+        }
+    }
+    anyhow::bail!("protobuf field {field} not found in response")
+}
+
+/// Reads a base-128 varint, returning its value and the remaining bytes.
+fn read_varint(bytes: &[u8]) -> anyhow::Result<(u64, &[u8])> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    for (i, &byte) in bytes.iter().enumerate() {
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, &bytes[i + 1..]));
+        }
+        shift += 7;
+        anyhow::ensure!(shift < 64, "varint overflow in protobuf response");
+    }
+    anyhow::bail!("unterminated varint in protobuf response")
+}
+
+/// The subset of CometBFT's `abci_query` JSON-RPC response we care about: the result code and log
+/// (for error reporting) and the base64-encoded protobuf `value`.
+#[derive(serde::Deserialize)]
+struct AbciQueryEnvelope {
+    result: AbciQueryResult,
+}
+
+#[derive(serde::Deserialize)]
+struct AbciQueryResult {
+    response: AbciQueryResponse,
+}
+
+#[derive(serde::Deserialize)]
+struct AbciQueryResponse {
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    log: String,
+    value: Option<String>,
+}
+
 fn check_contract(
     path: &str,
+    wasm: &[u8],
     available_capabilities: &HashSet<String>,
     verbose: bool,
     wasm_limits: &WasmLimits,
 ) -> anyhow::Result<()> {
-    let mut file = File::open(path)?;
-
-    // Read wasm
-    let mut wasm = Vec::<u8>::new();
-    file.read_to_end(&mut wasm)?;
-
     // Potentially lossy filename or path as used as a short prefix for the output
     let filename_identifier: String = Path::new(path)
         .file_name()
@@ -156,12 +669,98 @@ fn check_contract(
     } else {
         Logger::Off
     };
+
     // Check wasm
-    check_wasm_with_limits(&wasm, available_capabilities, wasm_limits, logs)?;
+    check_wasm_with_limits(wasm, available_capabilities, wasm_limits, logs)?;
 
     // Compile module
     let engine = make_compiling_engine(None);
-    let _module = compile(&engine, &wasm)?;
+    let _module = compile(&engine, wasm)?;
+
+    Ok(())
+}
+
+/// Prints everything `check_wasm_with_limits` discovers about a contract, without enforcing any
+/// capabilities. Unlike [`check_contract`] this never compiles the module, so it works on blobs
+/// that would fail the full check.
+fn inspect_contract(path: &str) -> anyhow::Result<()> {
+    let analysis = ContractAnalysis::from_wasm(&std::fs::read(path)?)?;
+
+    // Memory
+    println!(
+        "Memory: min {} pages, max {}",
+        analysis.memory_min,
+        analysis.format_memory_max()
+    );
+
+    // Exports (functions and globals)
+    println!("Exports:");
+    for (name, kind) in &analysis.exports {
+        println!("    {kind} {name}");
+    }
+
+    // Imports, grouped by module
+    println!("Imports:");
+    let mut by_module: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for import in &analysis.imports {
+        by_module
+            .entry(import.module.as_str())
+            .or_default()
+            .push(import.name.as_str());
+    }
+    for (module, names) in by_module {
+        println!("    {module}:");
+        for name in names {
+            println!("        {name}");
+        }
+    }
+
+    // Required capabilities
+    println!(
+        "Required capabilities: {}",
+        analysis
+            .required_capabilities
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
 
     Ok(())
 }
+
+impl ContractReport {
+    /// Builds a machine-readable report for a single contract from the check result and the
+    /// separate static analysis parsed alongside the check. The static analysis fields (memory, exports, imports, required
+    /// capabilities) are only populated when `analysis` is `Some`; if the wasm could not be parsed
+    /// they are left empty and only `error` is populated.
+    fn from_analysis(
+        path: &str,
+        available_capabilities: &HashSet<String>,
+        result: &anyhow::Result<()>,
+        analysis: Option<&ContractAnalysis>,
+    ) -> Self {
+        let mut available: Vec<String> = available_capabilities.iter().cloned().collect();
+        available.sort();
+
+        let mut report = ContractReport {
+            path: path.to_string(),
+            result: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            available_capabilities: available,
+            required_capabilities: Vec::new(),
+            memory_pages: 0,
+            exports: Vec::new(),
+            imports: Vec::new(),
+        };
+
+        if let Some(analysis) = analysis {
+            // The `BTreeSet`/`BTreeMap` fields already iterate in sorted order.
+            report.required_capabilities = analysis.required_capabilities.iter().cloned().collect();
+            report.memory_pages = analysis.memory_min as u32;
+            report.exports = analysis.exports.keys().cloned().collect();
+            report.imports = analysis.imports.iter().map(|i| i.to_string()).collect();
+        }
+        report
+    }
+}